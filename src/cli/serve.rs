@@ -3,10 +3,11 @@ use std::{
     net::{IpAddr, Ipv4Addr},
     path::PathBuf,
     sync::Arc,
+    thread,
 };
 
 use clap::Parser;
-use memofs::Vfs;
+use memofs::{Vfs, VfsEvent};
 use termcolor::{BufferWriter, Color, ColorChoice, ColorSpec, WriteColor};
 
 use crate::{git::GitFilter, serve_session::ServeSession, web::LiveServer};
@@ -59,7 +60,18 @@ impl ServeCommand {
                 "Git filter enabled: only syncing files changed since '{}'",
                 base_ref
             );
-            Some(Arc::new(GitFilter::new(repo_root, base_ref.clone(), &project_path)?))
+            let filter = Arc::new(GitFilter::new(repo_root, base_ref.clone(), &project_path)?);
+
+            // Watches .git's HEAD, index, and the base ref so that operations
+            // like `git commit` or `git checkout` are picked up even when
+            // they don't touch any tracked source file, and forwards matching
+            // changes to `notify_git_dir_changed`. This runs on its own `Vfs`
+            // instance rather than the one passed to `ServeSession` below, so
+            // it never competes with the sync session for events off the same
+            // receiver.
+            watch_git_dir(&filter);
+
+            Some(filter)
         } else {
             None
         };
@@ -85,6 +97,32 @@ impl ServeCommand {
     }
 }
 
+/// Watches a `GitFilter`'s repositories' `.git` directories on a dedicated
+/// `Vfs` instance and forwards matching changes to `notify_git_dir_changed`,
+/// which debounces them into a single `request_refresh()`. Runs for the
+/// lifetime of the process; failures to watch an individual path are logged
+/// and otherwise non-fatal, since the filter still works off of tracked
+/// source file changes in that case.
+fn watch_git_dir(filter: &Arc<GitFilter>) {
+    let git_vfs = Vfs::new_default();
+
+    for path in filter.git_dir_watch_paths() {
+        if let Err(err) = git_vfs.watch(&path) {
+            log::warn!("Failed to watch git path {}: {}", path.display(), err);
+        }
+    }
+
+    let filter = Arc::clone(filter);
+    thread::spawn(move || {
+        for event in git_vfs.event_receiver().iter() {
+            let path = match &event {
+                VfsEvent::Write(path) | VfsEvent::Create(path) | VfsEvent::Remove(path) => path,
+            };
+            filter.notify_git_dir_changed(path);
+        }
+    });
+}
+
 fn show_start_message(
     bind_address: IpAddr,
     port: u16,
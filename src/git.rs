@@ -1,13 +1,50 @@
 //! Git integration for filtering files based on changes since a reference.
 
 use std::{
-    collections::HashSet,
+    collections::{HashMap, HashSet},
     path::{Path, PathBuf},
     process::Command,
-    sync::{Arc, RwLock},
+    sync::{Arc, Mutex, RwLock},
+    thread,
+    time::Duration,
 };
 
 use anyhow::{bail, Context};
+use gix::ThreadSafeRepository;
+use serde::Serialize;
+
+/// The kind of change Git reports for a path that differs from `base_ref`.
+///
+/// `GitFilter::status_for`/`all_statuses` are the only things that read this.
+/// It is not included in the instance payloads that `ServeSession` and the
+/// `web` module send to Studio. `Serialize` is derived so that it can be
+/// serialized as part of such a payload.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub enum GitStatus {
+    /// The path exists in the worktree/index but not in `base_ref`.
+    Added,
+    /// The path exists in both but its contents differ.
+    Modified,
+    /// The path exists in `base_ref` but not in the worktree/index.
+    Deleted,
+    /// The path is untracked (not in `base_ref` and not in the index).
+    Untracked,
+    /// The path matches `base_ref`; this is the default for any path that
+    /// hasn't been reported as changed.
+    Unmodified,
+}
+
+/// Number of paths merged into the shared caches per batch during a refresh.
+/// Keeping batches small means `is_acknowledged` readers only ever wait on a
+/// write lock briefly, even while a large repository is being refreshed.
+const REFRESH_BATCH_SIZE: usize = 500;
+
+/// How long to wait after the last observed change under `.git` before
+/// triggering a refresh. A single `git` operation (commit, checkout, reset)
+/// touches `HEAD`, the index, and one or more refs in quick succession, and
+/// this collapses all of that into a single refresh.
+const GIT_DIR_DEBOUNCE: Duration = Duration::from_millis(200);
 
 /// A filter that tracks which files have been changed since a Git reference.
 ///
@@ -18,22 +55,28 @@ use anyhow::{bail, Context};
 /// Once a file is acknowledged (either initially or during the session), it
 /// stays acknowledged for the entire session. This prevents files from being
 /// deleted in Studio if their content is reverted to match the git reference.
+///
+/// A project isn't always backed by a single repository: it may pull in
+/// sources from submodules or sibling repositories. `GitFilter` keeps one
+/// [`RepoEntry`] per work-directory root it has seen and routes each path to
+/// the right one, discovering new repositories lazily as paths are
+/// acknowledged.
 #[derive(Debug)]
 pub struct GitFilter {
-    /// The Git repository root directory.
-    repo_root: PathBuf,
-
-    /// The Git reference to compare against (e.g., "HEAD", "main", a commit hash).
-    base_ref: String,
+    /// The work-directory root the filter was originally constructed with.
+    root_repo_root: PathBuf,
 
-    /// Cache of paths that are currently different from the base ref according to git.
-    /// This is refreshed on every VFS event.
-    git_changed_paths: RwLock<HashSet<PathBuf>>,
+    /// The project-level Git reference (e.g., "HEAD", "main", a commit
+    /// hash). Used as `base_ref` for the root repository, and for each
+    /// repository discovered later in which it resolves; see
+    /// `resolve_base_ref_for`.
+    default_base_ref: String,
 
-    /// Paths that have been acknowledged at any point during this session.
-    /// Once a path is added here, it stays acknowledged forever (for this session).
-    /// This prevents files from being deleted if their content is reverted.
-    session_acknowledged_paths: RwLock<HashSet<PathBuf>>,
+    /// Per-repository state, keyed by that repository's work-directory
+    /// root. The entry for `root_repo_root` always exists; entries for
+    /// nested repositories (submodules, or sibling repos pulled in as extra
+    /// sources) are added lazily by `repo_entry_for`.
+    repos: RwLock<HashMap<PathBuf, Arc<RepoEntry>>>,
 }
 
 impl GitFilter {
@@ -44,11 +87,12 @@ impl GitFilter {
     /// The `project_path` is the path to the project being served - it will always be
     /// acknowledged regardless of git status to ensure the project structure exists.
     pub fn new(repo_root: PathBuf, base_ref: String, project_path: &Path) -> anyhow::Result<Self> {
+        let root_entry = Arc::new(RepoEntry::new(repo_root.clone(), base_ref.clone()));
+
         let filter = Self {
-            repo_root,
-            base_ref,
-            git_changed_paths: RwLock::new(HashSet::new()),
-            session_acknowledged_paths: RwLock::new(HashSet::new()),
+            root_repo_root: repo_root.clone(),
+            default_base_ref: base_ref,
+            repos: RwLock::new(HashMap::from([(repo_root, Arc::clone(&root_entry))])),
         };
 
         // Always acknowledge the project path and its directory so the project
@@ -56,18 +100,376 @@ impl GitFilter {
         filter.acknowledge_project_path(project_path);
 
         // Initial refresh to populate the cache with git changes
-        filter.refresh()?;
+        root_entry.refresh()?;
 
         Ok(filter)
     }
 
+    /// Acknowledges the project path and its containing directory.
+    /// This ensures the project structure always exists regardless of git status.
+    fn acknowledge_project_path(&self, project_path: &Path) {
+        self.repo_entry_for(project_path)
+            .acknowledge_project_path(project_path);
+    }
+
+    /// Returns the per-repository entry that should handle `path`, opening
+    /// and caching one lazily if `path` belongs to a repository other than
+    /// any seen so far (e.g. a submodule, or a sibling repo pulled in as an
+    /// extra source).
+    ///
+    /// This is called once per acknowledged path per sync, so the common
+    /// case (a path under an already-known repo) stays an in-memory lookup
+    /// plus a check for a `.git` entry in each directory between `path` and
+    /// that repo's root: it calls `find_repo_root` (which can run
+    /// `gix::discover` or spawn `git`) only when `path` doesn't fall under
+    /// anything we've seen yet, or when such a `.git` entry is found.
+    fn repo_entry_for(&self, path: &Path) -> Arc<RepoEntry> {
+        {
+            let repos = self.repos.read().unwrap();
+            if let Some(root) = longest_known_prefix(path, repos.keys()) {
+                // A `.git` below the known root means `path` is inside a
+                // nested repository (e.g. a submodule) not seen yet.
+                let in_nested_repo = path
+                    .ancestors()
+                    .take_while(|dir| *dir != root.as_path())
+                    .any(|dir| dir.join(".git").exists());
+                if !in_nested_repo {
+                    return Arc::clone(&repos[&root]);
+                }
+            }
+        }
+
+        // Slow path: `path` isn't under any repo we've seen yet, or is inside
+        // a nested one. Only now do we pay for `find_repo_root`.
+        let work_dir = Self::find_repo_root(path).unwrap_or_else(|_| self.root_repo_root.clone());
+
+        if let Some(entry) = self.repos.read().unwrap().get(&work_dir) {
+            return Arc::clone(entry);
+        }
+
+        log::info!(
+            "Discovered a separate Git repository at {} (submodule or sibling repo)",
+            work_dir.display()
+        );
+
+        // Build and refresh the new entry outside the write lock: `refresh`
+        // does a full git-status walk of the new repo, and `repos` is a
+        // single lock shared by every already-known repository, so holding
+        // the write guard across it would block `is_acknowledged`/`status_for`
+        // calls against those repos for as long as the walk takes.
+        let base_ref = self.resolve_base_ref_for(&work_dir);
+        let entry = Arc::new(RepoEntry::new(work_dir.clone(), base_ref));
+        if let Err(err) = entry.refresh() {
+            log::warn!(
+                "Initial git status refresh for {} failed: {}",
+                work_dir.display(),
+                err
+            );
+        }
+
+        // Another thread may have raced us to discover the same repository;
+        // keep whichever entry got there first so every caller agrees on a
+        // single `RepoEntry` for a given root.
+        let mut repos = self.repos.write().unwrap();
+        Arc::clone(repos.entry(work_dir).or_insert(entry))
+    }
+
+    /// Resolves `default_base_ref` against the repository rooted at
+    /// `work_dir`, falling back to `"HEAD"` if it doesn't resolve there.
+    /// This happens for e.g. a submodule that's on a detached commit, or a
+    /// sibling repo that doesn't have a branch of the same name as the
+    /// project's base ref - `HEAD` always resolves in a non-empty repo.
+    fn resolve_base_ref_for(&self, work_dir: &Path) -> String {
+        let resolves = gix::open(work_dir)
+            .ok()
+            .and_then(|repo| repo.rev_parse_single(self.default_base_ref.as_str()).ok())
+            .is_some();
+
+        if resolves {
+            self.default_base_ref.clone()
+        } else {
+            log::debug!(
+                "Base ref '{}' doesn't resolve in {}, falling back to HEAD",
+                self.default_base_ref,
+                work_dir.display()
+            );
+            "HEAD".to_owned()
+        }
+    }
+
+    /// Finds the Git repository root for the given path.
+    pub fn find_repo_root(path: &Path) -> anyhow::Result<PathBuf> {
+        if let Ok(repo) = gix::discover(path) {
+            if let Some(work_dir) = repo.work_dir() {
+                return Ok(work_dir.to_path_buf());
+            }
+        }
+
+        Self::find_repo_root_via_subprocess(path)
+    }
+
+    /// Finds the Git repository root for the given path by shelling out to `git`.
+    ///
+    /// Used as a fallback when `gix::discover` can't make sense of the worktree.
+    fn find_repo_root_via_subprocess(path: &Path) -> anyhow::Result<PathBuf> {
+        let output = Command::new("git")
+            .args(["rev-parse", "--show-toplevel"])
+            .current_dir(path)
+            .output()
+            .context("Failed to execute git rev-parse")?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            bail!("Failed to find Git repository root: {}", stderr.trim());
+        }
+
+        let root = String::from_utf8_lossy(&output.stdout).trim().to_string();
+
+        Ok(PathBuf::from(root))
+    }
+
+    /// Refreshes every known repository's acknowledged paths by querying Git.
+    ///
+    /// This should be called when files change to ensure newly modified files
+    /// are properly acknowledged. Once a path is acknowledged, it stays
+    /// acknowledged for the entire session (even if the file is reverted).
+    ///
+    /// Failures in a nested repository (submodule or sibling repo) are
+    /// logged and otherwise ignored; a failure in the project's root
+    /// repository is returned, since that one is required for the session
+    /// to make sense at all.
+    pub fn refresh(&self) -> anyhow::Result<()> {
+        let repos: Vec<_> = self.repos.read().unwrap().values().cloned().collect();
+
+        let mut root_result = Ok(());
+        for entry in repos {
+            match entry.refresh() {
+                Ok(()) => {}
+                Err(err) if entry.repo_root == self.root_repo_root => root_result = Err(err),
+                Err(err) => log::warn!(
+                    "Git status refresh for {} failed: {}",
+                    entry.repo_root.display(),
+                    err
+                ),
+            }
+        }
+
+        root_result
+    }
+
+    /// Requests a background refresh of every known repository, coalescing
+    /// concurrent requests per-repository into a single in-flight worker
+    /// rather than running refreshes back-to-back.
+    pub fn request_refresh(self: &Arc<Self>) {
+        let repos: Vec<_> = self.repos.read().unwrap().values().cloned().collect();
+        for entry in repos {
+            entry.request_refresh();
+        }
+    }
+
+    /// Returns the paths under each known repository's `.git` directory
+    /// that should be watched so that git operations which don't touch any
+    /// tracked source file (a `commit`, `reset`, `checkout`, or staging via
+    /// the index) still trigger a refresh. `memofs`'s VFS walker normally
+    /// ignores `.git` entirely, so these need to be watched explicitly.
+    ///
+    /// Repositories discovered after this is called (e.g. a submodule only
+    /// found once a path inside it is acknowledged) aren't covered until
+    /// this is called again.
+    pub fn git_dir_watch_paths(&self) -> Vec<PathBuf> {
+        self.repos
+            .read()
+            .unwrap()
+            .values()
+            .flat_map(|entry| entry.git_dir_watch_paths())
+            .collect()
+    }
+
+    /// Notifies the filter that a watched path under some repository's
+    /// `.git` directory changed, debouncing it into a single refresh of
+    /// that repository. Paths that don't fall under any known repository's
+    /// `.git` directory are ignored.
+    pub fn notify_git_dir_changed(self: &Arc<Self>, changed_path: &Path) {
+        let entry = self
+            .repos
+            .read()
+            .unwrap()
+            .values()
+            .find(|entry| changed_path.starts_with(entry.repo_root.join(".git")))
+            .cloned();
+
+        match entry {
+            Some(entry) => entry.notify_git_dir_changed(),
+            None => log::trace!(
+                "Ignoring .git change at {} (no matching repository)",
+                changed_path.display()
+            ),
+        }
+    }
+
+    /// Checks if a path is acknowledged (should be synced).
+    ///
+    /// Returns `true` if the path or any of its descendants have been changed
+    /// at any point during this session. Once a file is acknowledged, it stays
+    /// acknowledged even if its content is reverted to match the git reference.
+    pub fn is_acknowledged(&self, path: &Path) -> bool {
+        self.repo_entry_for(path).is_acknowledged(path)
+    }
+
+    /// Returns the kind of change Git reports for `path` as of the last
+    /// refresh, or `GitStatus::Unmodified` if it isn't currently changed
+    /// (including for paths that were never tracked by this filter at all).
+    pub fn status_for(&self, path: &Path) -> GitStatus {
+        self.repo_entry_for(path).status_for(path)
+    }
+
+    /// Returns every path (across all known repositories) that's currently
+    /// reported as changed, along with its `GitStatus`, as of the last
+    /// refresh of each repository.
+    ///
+    /// This is the bulk form of `status_for`, meant for building a payload
+    /// that reports status for many paths at once (e.g. a sync message) and
+    /// not for this single-path-at-a-time filter's own routing decisions.
+    pub fn all_statuses(&self) -> Vec<(PathBuf, GitStatus)> {
+        self.repos
+            .read()
+            .unwrap()
+            .values()
+            .flat_map(|entry| entry.all_statuses())
+            .collect()
+    }
+
+    /// Returns the project-level base reference being compared against.
+    pub fn base_ref(&self) -> &str {
+        &self.default_base_ref
+    }
+
+    /// Returns the root repository's root path.
+    pub fn repo_root(&self) -> &Path {
+        &self.root_repo_root
+    }
+
+    /// Explicitly acknowledges a path and all its ancestors.
+    /// This is useful for ensuring certain paths are always synced regardless of git status.
+    pub fn force_acknowledge(&self, path: &Path) {
+        self.repo_entry_for(path).force_acknowledge(path);
+    }
+}
+
+/// Per-repository Git state: everything `GitFilter` used to hold directly,
+/// before it needed to track more than one repository at once.
+struct RepoEntry {
+    /// The Git repository's work-directory root.
+    repo_root: PathBuf,
+
+    /// A handle to the repository opened with `gix`, used to compute status
+    /// in-process. `None` when the repository could not be opened with `gix`
+    /// (e.g. an unusual worktree layout), in which case we fall back to
+    /// shelling out to the `git` binary.
+    repo: Option<ThreadSafeRepository>,
+
+    /// The Git reference to compare against (e.g., "HEAD", "main", a commit hash).
+    base_ref: String,
+
+    /// Cache of paths that are currently different from the base ref according to git.
+    /// This is refreshed on every VFS event.
+    git_changed_paths: RwLock<HashSet<PathBuf>>,
+
+    /// Paths that have been acknowledged at any point during this session.
+    /// Once a path is added here, it stays acknowledged forever (for this session).
+    /// This prevents files from being deleted if their content is reverted.
+    session_acknowledged_paths: RwLock<HashSet<PathBuf>>,
+
+    /// The kind of change for each path currently reported as different from
+    /// `base_ref`. Unlike `session_acknowledged_paths`, this reflects the
+    /// most recent refresh only: a path that's since been reverted is
+    /// removed, so `status_for` falls back to `GitStatus::Unmodified`.
+    git_status: RwLock<HashMap<PathBuf, GitStatus>>,
+
+    /// Coordinates background refreshes triggered by `request_refresh`, so
+    /// that concurrent requests coalesce into a single in-flight worker
+    /// instead of running refreshes back-to-back.
+    refresh_coordinator: Mutex<RefreshCoordinator>,
+
+    /// Generation counter used to debounce `.git` directory watch events;
+    /// see `notify_git_dir_changed`.
+    git_dir_debounce_generation: Mutex<u64>,
+}
+
+/// Tracks whether a background refresh is running and whether another one
+/// has been requested since it started.
+#[derive(Debug, Default)]
+struct RefreshCoordinator {
+    /// Set whenever `request_refresh` is called while a worker is already
+    /// running, so that worker knows to do one more pass before stopping.
+    dirty: bool,
+
+    /// Whether a background refresh worker is currently running.
+    in_flight: bool,
+}
+
+impl std::fmt::Debug for RepoEntry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RepoEntry")
+            .field("repo_root", &self.repo_root)
+            .field("repo", &self.repo.is_some())
+            .field("base_ref", &self.base_ref)
+            .field("git_changed_paths", &self.git_changed_paths)
+            .field(
+                "session_acknowledged_paths",
+                &self.session_acknowledged_paths,
+            )
+            .field("git_status", &self.git_status)
+            .field(
+                "refresh_coordinator",
+                &*self.refresh_coordinator.lock().unwrap(),
+            )
+            .field(
+                "git_dir_debounce_generation",
+                &*self.git_dir_debounce_generation.lock().unwrap(),
+            )
+            .finish()
+    }
+}
+
+impl RepoEntry {
+    /// Opens `repo_root` with `gix`, falling back to shelling out to `git`
+    /// for status computation if that fails. Does not perform an initial
+    /// refresh; callers do that once the entry is constructed.
+    fn new(repo_root: PathBuf, base_ref: String) -> Self {
+        let repo = match gix::open(&repo_root) {
+            Ok(repo) => Some(repo.into_sync()),
+            Err(err) => {
+                log::warn!(
+                    "Failed to open {} with gix ({}), falling back to the git CLI",
+                    repo_root.display(),
+                    err
+                );
+                None
+            }
+        };
+
+        Self {
+            repo_root,
+            repo,
+            base_ref,
+            git_changed_paths: RwLock::new(HashSet::new()),
+            session_acknowledged_paths: RwLock::new(HashSet::new()),
+            git_status: RwLock::new(HashMap::new()),
+            refresh_coordinator: Mutex::new(RefreshCoordinator::default()),
+            git_dir_debounce_generation: Mutex::new(0),
+        }
+    }
+
     /// Acknowledges the project path and its containing directory.
     /// This ensures the project structure always exists regardless of git status.
     fn acknowledge_project_path(&self, project_path: &Path) {
         let mut session = self.session_acknowledged_paths.write().unwrap();
 
         // Acknowledge the project path itself (might be a directory or .project.json file)
-        let canonical = project_path.canonicalize().unwrap_or_else(|_| project_path.to_path_buf());
+        let canonical = project_path
+            .canonicalize()
+            .unwrap_or_else(|_| project_path.to_path_buf());
         session.insert(canonical.clone());
 
         // Acknowledge all ancestor directories
@@ -91,7 +493,9 @@ impl GitFilter {
 
         // If it's a .project.json file, also acknowledge its parent directory
         if let Some(parent) = project_path.parent() {
-            let parent_canonical = parent.canonicalize().unwrap_or_else(|_| parent.to_path_buf());
+            let parent_canonical = parent
+                .canonicalize()
+                .unwrap_or_else(|_| parent.to_path_buf());
             session.insert(parent_canonical);
         }
 
@@ -102,37 +506,263 @@ impl GitFilter {
         );
     }
 
-    /// Finds the Git repository root for the given path.
-    pub fn find_repo_root(path: &Path) -> anyhow::Result<PathBuf> {
-        let output = Command::new("git")
-            .args(["rev-parse", "--show-toplevel"])
-            .current_dir(path)
-            .output()
-            .context("Failed to execute git rev-parse")?;
+    /// Refreshes the cache of acknowledged paths by querying Git.
+    ///
+    /// This should be called when files change to ensure newly modified files
+    /// are properly acknowledged. Once a path is acknowledged, it stays
+    /// acknowledged for the entire session (even if the file is reverted).
+    ///
+    /// This runs synchronously on the calling thread and merges the whole
+    /// result at once; prefer `request_refresh` for refreshes triggered from
+    /// VFS events, which runs on a background thread in batches so it can't
+    /// stall sync processing on a large repository.
+    fn refresh(&self) -> anyhow::Result<()> {
+        let git_status = self.compute_git_status()?;
+        self.merge_git_status(git_status);
+        Ok(())
+    }
 
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            bail!("Failed to find Git repository root: {}", stderr.trim());
+    /// Requests a refresh on a background thread, coalescing concurrent
+    /// requests into a single in-flight worker rather than running refreshes
+    /// back-to-back.
+    ///
+    /// If a worker is already running when this is called, it is marked
+    /// dirty and will perform one more pass before stopping, so the caller
+    /// never needs to wait for a dedicated refresh of its own.
+    fn request_refresh(self: &Arc<Self>) {
+        let mut coordinator = self.refresh_coordinator.lock().unwrap();
+        coordinator.dirty = true;
+        if coordinator.in_flight {
+            return;
         }
+        coordinator.in_flight = true;
+        drop(coordinator);
 
-        let root = String::from_utf8_lossy(&output.stdout)
-            .trim()
-            .to_string();
+        let entry = Arc::clone(self);
+        thread::spawn(move || entry.run_refresh_worker());
+    }
 
-        Ok(PathBuf::from(root))
+    /// Background worker body for `request_refresh`. Keeps refreshing until
+    /// no further refresh was requested while the previous one was running.
+    fn run_refresh_worker(self: Arc<Self>) {
+        loop {
+            self.refresh_coordinator.lock().unwrap().dirty = false;
+
+            match self.compute_git_status() {
+                Ok(git_status) => self.merge_git_status_batched(git_status),
+                Err(err) => log::error!(
+                    "Background git status refresh for {} failed: {}",
+                    self.repo_root.display(),
+                    err
+                ),
+            }
+
+            let mut coordinator = self.refresh_coordinator.lock().unwrap();
+            if coordinator.dirty {
+                // Another refresh was requested while this one was running;
+                // go again instead of leaving it unserved.
+                continue;
+            }
+            coordinator.in_flight = false;
+            break;
+        }
     }
 
-    /// Refreshes the cache of acknowledged paths by querying Git.
+    /// Returns the paths under `.git` that should be watched so that git
+    /// operations which don't touch any tracked source file (a `commit`,
+    /// `reset`, `checkout`, or staging via the index) still trigger a
+    /// refresh. `memofs`'s VFS walker normally ignores `.git` entirely, so
+    /// these need to be watched explicitly, via `notify_git_dir_changed`.
+    fn git_dir_watch_paths(&self) -> Vec<PathBuf> {
+        let git_dir = self.repo_root.join(".git");
+        let mut paths = vec![git_dir.join("HEAD"), git_dir.join("index")];
+
+        if let Some(repo) = &self.repo {
+            let repo = repo.to_thread_local();
+            if let Ok(reference) = repo.find_reference(self.base_ref.as_str()) {
+                paths.push(git_dir.join(reference.name().as_bstr().to_string()));
+            }
+        }
+
+        paths
+    }
+
+    /// Notifies the entry that one of `git_dir_watch_paths` changed.
     ///
-    /// This should be called when files change to ensure newly modified files
-    /// are properly acknowledged. Once a path is acknowledged, it stays
-    /// acknowledged for the entire session (even if the file is reverted).
-    pub fn refresh(&self) -> anyhow::Result<()> {
+    /// A single git operation touches several of those paths in quick
+    /// succession (e.g. `HEAD`, the index, and a ref all change during one
+    /// `commit`), so this debounces: only the last call within
+    /// `GIT_DIR_DEBOUNCE` actually triggers a `request_refresh`.
+    fn notify_git_dir_changed(self: &Arc<Self>) {
+        let generation = {
+            let mut generation = self.git_dir_debounce_generation.lock().unwrap();
+            *generation += 1;
+            *generation
+        };
+
+        let entry = Arc::clone(self);
+        thread::spawn(move || {
+            thread::sleep(GIT_DIR_DEBOUNCE);
+
+            let current_generation = *entry.git_dir_debounce_generation.lock().unwrap();
+            if current_generation == generation {
+                entry.request_refresh();
+            }
+        });
+    }
+
+    /// Computes the current status of every changed path, preferring `gix`
+    /// and falling back to the `git` CLI.
+    fn compute_git_status(&self) -> anyhow::Result<HashMap<PathBuf, GitStatus>> {
+        match &self.repo {
+            Some(repo) => self.refresh_via_gix(repo).or_else(|err| {
+                log::warn!(
+                    "gix status failed ({}), falling back to the git CLI for this refresh",
+                    err
+                );
+                self.refresh_via_subprocess()
+            }),
+            None => self.refresh_via_subprocess(),
+        }
+    }
+
+    /// Expands each changed path into the full acknowledged set: the path
+    /// itself, its ancestor directories, and its associated meta files.
+    fn expand_acknowledged(&self, git_status: &HashMap<PathBuf, GitStatus>) -> HashSet<PathBuf> {
         let mut git_changed = HashSet::new();
+        for path in git_status.keys() {
+            acknowledge_path(path, &mut git_changed);
+        }
+        git_changed
+    }
+
+    /// Merges a freshly computed git status map into the caches in one go.
+    fn merge_git_status(&self, git_status: HashMap<PathBuf, GitStatus>) {
+        let git_changed = self.expand_acknowledged(&git_status);
+
+        {
+            let mut cache = self.git_changed_paths.write().unwrap();
+            *cache = git_changed.clone();
+        }
+
+        {
+            let mut status_cache = self.git_status.write().unwrap();
+            *status_cache = git_status;
+        }
+
+        // Merge newly changed paths into session acknowledged paths
+        // Once acknowledged, a path stays acknowledged for the entire session
+        {
+            let mut session = self.session_acknowledged_paths.write().unwrap();
+            for path in git_changed {
+                session.insert(path);
+            }
+            log::debug!(
+                "GitFilter refreshed {}: {} paths acknowledged in session",
+                self.repo_root.display(),
+                session.len()
+            );
+        }
+    }
+
+    /// Merges a freshly computed git status map into the caches in fixed
+    /// size batches, only ever holding the write locks briefly so
+    /// `is_acknowledged` readers stay responsive during a large refresh.
+    ///
+    /// Paths are only ever inserted into the session set during this
+    /// process, never removed, so the monotonic "once acknowledged, always
+    /// acknowledged" invariant holds even if readers observe a partially
+    /// merged refresh.
+    fn merge_git_status_batched(&self, git_status: HashMap<PathBuf, GitStatus>) {
+        let git_changed = self.expand_acknowledged(&git_status);
+
+        let mut new_cache = HashSet::with_capacity(git_changed.len());
+        let mut batch = Vec::with_capacity(REFRESH_BATCH_SIZE);
+
+        for path in git_changed {
+            new_cache.insert(path.clone());
+            batch.push(path);
+
+            if batch.len() >= REFRESH_BATCH_SIZE {
+                self.merge_session_batch(&mut batch);
+                thread::yield_now();
+            }
+        }
+        if !batch.is_empty() {
+            self.merge_session_batch(&mut batch);
+        }
+
+        // Both caches reflect a single point-in-time diff, so unlike the
+        // session set they're swapped in wholesale once the whole refresh
+        // has completed rather than merged batch by batch.
+        let mut cache = self.git_changed_paths.write().unwrap();
+        *cache = new_cache;
+
+        let mut status_cache = self.git_status.write().unwrap();
+        *status_cache = git_status;
+
+        log::debug!(
+            "GitFilter refreshed {} in batches of {}",
+            self.repo_root.display(),
+            REFRESH_BATCH_SIZE
+        );
+    }
+
+    /// Inserts one batch of paths into `session_acknowledged_paths`, holding
+    /// the write lock only for the duration of the batch, then clears it.
+    fn merge_session_batch(&self, batch: &mut Vec<PathBuf>) {
+        let mut session = self.session_acknowledged_paths.write().unwrap();
+        for path in batch.drain(..) {
+            session.insert(path);
+        }
+    }
+
+    /// Computes the status of every changed path in-process using `gix`,
+    /// comparing `base_ref` against the worktree (and the index, for staged
+    /// changes).
+    fn refresh_via_gix(
+        &self,
+        repo: &ThreadSafeRepository,
+    ) -> anyhow::Result<HashMap<PathBuf, GitStatus>> {
+        let repo = repo.to_thread_local();
+
+        let base_tree = repo
+            .rev_parse_single(self.base_ref.as_str())?
+            .object()?
+            .peel_to_tree()?;
+
+        let mut git_status = HashMap::new();
+
+        let status = repo
+            .status(gix::progress::Discard)?
+            .into_iter(Some(base_tree.id()))
+            .context("Failed to compute git status with gix")?;
+
+        for item in status {
+            let item = item?;
+            let path = self
+                .repo_root
+                .join(item.location().to_path_lossy().as_ref());
+            let path = path.canonicalize().unwrap_or(path);
+            let status = classify_gix_status(&item);
+            log::trace!("gix status: {} is {:?}", path.display(), status);
+            git_status.insert(path, status);
+        }
+
+        Ok(git_status)
+    }
+
+    /// Computes the status of every changed path by shelling out to the
+    /// `git` binary.
+    ///
+    /// This is the fallback used when the repository can't be opened with
+    /// `gix` (e.g. unusual worktree configurations).
+    fn refresh_via_subprocess(&self) -> anyhow::Result<HashMap<PathBuf, GitStatus>> {
+        let mut git_status = HashMap::new();
 
         // Get files changed since the base ref (modified, added, deleted)
         let diff_output = Command::new("git")
-            .args(["diff", "--name-only", &self.base_ref])
+            .args(["diff", "--name-status", &self.base_ref])
             .current_dir(&self.repo_root)
             .output()
             .context("Failed to execute git diff")?;
@@ -142,16 +772,17 @@ impl GitFilter {
             bail!("git diff failed: {}", stderr.trim());
         }
 
-        let diff_files = String::from_utf8_lossy(&diff_output.stdout);
-        let diff_count = diff_files.lines().filter(|l| !l.is_empty()).count();
+        let diff_lines = String::from_utf8_lossy(&diff_output.stdout);
+        let diff_count = diff_lines.lines().filter(|l| !l.is_empty()).count();
         if diff_count > 0 {
             log::debug!("git diff found {} changed files", diff_count);
         }
-        for line in diff_files.lines() {
-            if !line.is_empty() {
-                let path = self.repo_root.join(line);
-                log::trace!("git diff: acknowledging {}", path.display());
-                self.acknowledge_path(&path, &mut git_changed);
+        for line in diff_lines.lines() {
+            if let Some((status, name)) = parse_name_status_line(line) {
+                let path = self.repo_root.join(name);
+                let path = path.canonicalize().unwrap_or(path);
+                log::trace!("git diff: {} is {:?}", path.display(), status);
+                git_status.insert(path, status);
             }
         }
 
@@ -171,94 +802,30 @@ impl GitFilter {
         for line in untracked_files.lines() {
             if !line.is_empty() {
                 let path = self.repo_root.join(line);
-                self.acknowledge_path(&path, &mut git_changed);
+                let path = path.canonicalize().unwrap_or(path);
+                git_status.insert(path, GitStatus::Untracked);
             }
         }
 
         // Get staged files (files added to index but not yet committed)
         let staged_output = Command::new("git")
-            .args(["diff", "--name-only", "--cached", &self.base_ref])
+            .args(["diff", "--name-status", "--cached", &self.base_ref])
             .current_dir(&self.repo_root)
             .output()
             .context("Failed to execute git diff --cached")?;
 
         if staged_output.status.success() {
-            let staged_files = String::from_utf8_lossy(&staged_output.stdout);
-            for line in staged_files.lines() {
-                if !line.is_empty() {
-                    let path = self.repo_root.join(line);
-                    self.acknowledge_path(&path, &mut git_changed);
+            let staged_lines = String::from_utf8_lossy(&staged_output.stdout);
+            for line in staged_lines.lines() {
+                if let Some((status, name)) = parse_name_status_line(line) {
+                    let path = self.repo_root.join(name);
+                    let path = path.canonicalize().unwrap_or(path);
+                    git_status.entry(path).or_insert(status);
                 }
             }
         }
 
-        // Update the git changed paths cache
-        {
-            let mut cache = self.git_changed_paths.write().unwrap();
-            *cache = git_changed.clone();
-        }
-
-        // Merge newly changed paths into session acknowledged paths
-        // Once acknowledged, a path stays acknowledged for the entire session
-        {
-            let mut session = self.session_acknowledged_paths.write().unwrap();
-            for path in git_changed {
-                session.insert(path);
-            }
-            log::debug!(
-                "GitFilter refreshed: {} paths acknowledged in session",
-                session.len()
-            );
-        }
-
-        Ok(())
-    }
-
-    /// Acknowledges a path and all its ancestors, plus associated meta files.
-    fn acknowledge_path(&self, path: &Path, acknowledged: &mut HashSet<PathBuf>) {
-        // Canonicalize the path if possible, otherwise use as-is
-        let path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
-
-        // Add the path itself
-        acknowledged.insert(path.clone());
-
-        // Add all ancestor directories
-        let mut current = path.parent();
-        while let Some(parent) = current {
-            acknowledged.insert(parent.to_path_buf());
-            current = parent.parent();
-        }
-
-        // Add associated meta files
-        self.acknowledge_meta_files(&path, acknowledged);
-    }
-
-    /// Acknowledges associated meta files for a given path.
-    fn acknowledge_meta_files(&self, path: &Path, acknowledged: &mut HashSet<PathBuf>) {
-        if let Some(file_name) = path.file_name().and_then(|n| n.to_str()) {
-            if let Some(parent) = path.parent() {
-                // For a file like "foo.lua", also acknowledge "foo.meta.json"
-                // Strip known extensions to get the base name
-                let base_name = strip_lua_extension(file_name);
-
-                let meta_path = parent.join(format!("{}.meta.json", base_name));
-                if let Ok(canonical) = meta_path.canonicalize() {
-                    acknowledged.insert(canonical);
-                } else {
-                    acknowledged.insert(meta_path);
-                }
-
-                // For init files, also acknowledge "init.meta.json" in the same directory
-                if file_name.starts_with("init.") {
-                    let init_meta = parent.join("init.meta.json");
-                    if let Ok(canonical) = init_meta.canonicalize() {
-                        acknowledged.insert(canonical);
-                    } else {
-                        acknowledged.insert(init_meta);
-                    }
-                }
-            }
-        }
+        Ok(git_status)
     }
 
     /// Checks if a path is acknowledged (should be synced).
@@ -266,7 +833,7 @@ impl GitFilter {
     /// Returns `true` if the path or any of its descendants have been changed
     /// at any point during this session. Once a file is acknowledged, it stays
     /// acknowledged even if its content is reverted to match the git reference.
-    pub fn is_acknowledged(&self, path: &Path) -> bool {
+    fn is_acknowledged(&self, path: &Path) -> bool {
         let session = self.session_acknowledged_paths.read().unwrap();
 
         // Try to canonicalize the path
@@ -314,21 +881,37 @@ impl GitFilter {
         false
     }
 
-    /// Returns the base reference being compared against.
-    pub fn base_ref(&self) -> &str {
-        &self.base_ref
+    /// Returns the kind of change Git reports for `path` as of the last
+    /// refresh, or `GitStatus::Unmodified` if it isn't currently changed
+    /// (including for paths that were never tracked by this filter at all).
+    fn status_for(&self, path: &Path) -> GitStatus {
+        let git_status = self.git_status.read().unwrap();
+
+        let canonical = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+
+        git_status
+            .get(&canonical)
+            .or_else(|| git_status.get(path))
+            .copied()
+            .unwrap_or(GitStatus::Unmodified)
     }
 
-    /// Returns the repository root path.
-    pub fn repo_root(&self) -> &Path {
-        &self.repo_root
+    /// Returns every path in this repository currently reported as changed,
+    /// along with its `GitStatus`, as of the last refresh.
+    fn all_statuses(&self) -> Vec<(PathBuf, GitStatus)> {
+        self.git_status
+            .read()
+            .unwrap()
+            .iter()
+            .map(|(path, status)| (path.clone(), *status))
+            .collect()
     }
 
     /// Explicitly acknowledges a path and all its ancestors.
     /// This is useful for ensuring certain paths are always synced regardless of git status.
-    pub fn force_acknowledge(&self, path: &Path) {
+    fn force_acknowledge(&self, path: &Path) {
         let mut acknowledged = HashSet::new();
-        self.acknowledge_path(path, &mut acknowledged);
+        acknowledge_path(path, &mut acknowledged);
 
         let mut session = self.session_acknowledged_paths.write().unwrap();
         for p in acknowledged {
@@ -337,6 +920,105 @@ impl GitFilter {
     }
 }
 
+/// Returns the most specific (longest) of `known_roots` that encloses
+/// `path`, or `None` if no known root does. Used by `repo_entry_for` to
+/// route a path to its repository without touching the filesystem.
+fn longest_known_prefix<'a>(
+    path: &Path,
+    known_roots: impl Iterator<Item = &'a PathBuf>,
+) -> Option<PathBuf> {
+    known_roots
+        .filter(|root| path.starts_with(root.as_path()))
+        .max_by_key(|root| root.as_os_str().len())
+        .cloned()
+}
+
+/// Acknowledges a path and all its ancestors, plus associated meta files.
+fn acknowledge_path(path: &Path, acknowledged: &mut HashSet<PathBuf>) {
+    // Canonicalize the path if possible, otherwise use as-is
+    let path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+
+    // Add the path itself
+    acknowledged.insert(path.clone());
+
+    // Add all ancestor directories
+    let mut current = path.parent();
+    while let Some(parent) = current {
+        acknowledged.insert(parent.to_path_buf());
+        current = parent.parent();
+    }
+
+    // Add associated meta files
+    acknowledge_meta_files(&path, acknowledged);
+}
+
+/// Acknowledges associated meta files for a given path.
+fn acknowledge_meta_files(path: &Path, acknowledged: &mut HashSet<PathBuf>) {
+    if let Some(file_name) = path.file_name().and_then(|n| n.to_str()) {
+        if let Some(parent) = path.parent() {
+            // For a file like "foo.lua", also acknowledge "foo.meta.json"
+            // Strip known extensions to get the base name
+            let base_name = strip_lua_extension(file_name);
+
+            let meta_path = parent.join(format!("{}.meta.json", base_name));
+            if let Ok(canonical) = meta_path.canonicalize() {
+                acknowledged.insert(canonical);
+            } else {
+                acknowledged.insert(meta_path);
+            }
+
+            // For init files, also acknowledge "init.meta.json" in the same directory
+            if file_name.starts_with("init.") {
+                let init_meta = parent.join("init.meta.json");
+                if let Ok(canonical) = init_meta.canonicalize() {
+                    acknowledged.insert(canonical);
+                } else {
+                    acknowledged.insert(init_meta);
+                }
+            }
+        }
+    }
+}
+
+/// Classifies a `gix` status item into our coarser `GitStatus`.
+///
+/// `summary()` returns `None` when the item has no tree/index counterpart to
+/// compare against at all - that's exactly a worktree-only file Git doesn't
+/// track, so it's reported as `Untracked` rather than lumped in with
+/// `Modified`.
+fn classify_gix_status(item: &gix::status::Item) -> GitStatus {
+    use gix::status::index_worktree::iter::Summary;
+
+    match item.summary() {
+        Some(Summary::Added) | Some(Summary::IntentToAdd) => GitStatus::Added,
+        Some(Summary::Removed) => GitStatus::Deleted,
+        Some(Summary::Renamed) | Some(Summary::Copied) => GitStatus::Modified,
+        Some(Summary::Modified) | Some(Summary::TypeChange) => GitStatus::Modified,
+        None => GitStatus::Untracked,
+    }
+}
+
+/// Parses one line of `git diff --name-status` output into a `GitStatus`
+/// and the affected path. Handles plain "M\tpath" lines as well as rename
+/// and copy lines ("R100\told\tnew"), for which the new path is reported.
+fn parse_name_status_line(line: &str) -> Option<(GitStatus, &str)> {
+    let mut fields = line.split('\t');
+    let code = fields.next()?;
+    let name = fields.last()?;
+
+    if name.is_empty() {
+        return None;
+    }
+
+    let status = match code.chars().next()? {
+        'A' => GitStatus::Added,
+        'D' => GitStatus::Deleted,
+        _ => GitStatus::Modified,
+    };
+
+    Some((status, name))
+}
+
 /// Strips Lua-related extensions from a file name to get the base name.
 fn strip_lua_extension(file_name: &str) -> &str {
     const EXTENSIONS: &[&str] = &[
@@ -377,4 +1059,195 @@ mod tests {
         assert_eq!(strip_lua_extension("bar.txt"), "bar");
         assert_eq!(strip_lua_extension("noextension"), "noextension");
     }
+
+    #[test]
+    fn test_parse_name_status_line() {
+        assert_eq!(
+            parse_name_status_line("M\tsrc/foo.lua"),
+            Some((GitStatus::Modified, "src/foo.lua"))
+        );
+        assert_eq!(
+            parse_name_status_line("A\tsrc/new.lua"),
+            Some((GitStatus::Added, "src/new.lua"))
+        );
+        assert_eq!(
+            parse_name_status_line("D\tsrc/old.lua"),
+            Some((GitStatus::Deleted, "src/old.lua"))
+        );
+        // Rename/copy lines carry a similarity score after the status letter
+        // and the old name before the new one; only the new name is reported.
+        assert_eq!(
+            parse_name_status_line("R100\tsrc/old.lua\tsrc/new.lua"),
+            Some((GitStatus::Modified, "src/new.lua"))
+        );
+        assert_eq!(parse_name_status_line(""), None);
+    }
+
+    #[test]
+    fn test_longest_known_prefix() {
+        let roots = [
+            PathBuf::from("/repo"),
+            PathBuf::from("/repo/vendor/submodule"),
+        ];
+
+        // A path under the nested submodule should prefer the more specific root.
+        assert_eq!(
+            longest_known_prefix(
+                Path::new("/repo/vendor/submodule/src/foo.lua"),
+                roots.iter()
+            ),
+            Some(PathBuf::from("/repo/vendor/submodule"))
+        );
+
+        // A path outside the submodule falls back to the outer root.
+        assert_eq!(
+            longest_known_prefix(Path::new("/repo/src/foo.lua"), roots.iter()),
+            Some(PathBuf::from("/repo"))
+        );
+
+        // A path under neither root matches nothing.
+        assert_eq!(
+            longest_known_prefix(Path::new("/elsewhere/foo.lua"), roots.iter()),
+            None
+        );
+    }
+
+    /// Creates a fresh, uniquely-named directory under the system temp dir
+    /// for a test to set up a real repository in.
+    fn unique_temp_dir(label: &str) -> PathBuf {
+        use std::time::{SystemTime, UNIX_EPOCH};
+
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let dir = std::env::temp_dir().join(format!(
+            "rojo-git-test-{}-{}-{}",
+            label,
+            std::process::id(),
+            nanos
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    /// Runs `git` in `dir`, panicking if it doesn't succeed. Used to set up
+    /// fixture repositories for tests, mirroring how `refresh_via_subprocess`
+    /// already shells out to `git`.
+    fn run_git(dir: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .args(args)
+            .current_dir(dir)
+            .status()
+            .expect("failed to run git");
+        assert!(
+            status.success(),
+            "`git {:?}` failed in {}",
+            args,
+            dir.display()
+        );
+    }
+
+    fn init_repo(dir: &Path) {
+        run_git(dir, &["init", "-q"]);
+        run_git(dir, &["config", "user.email", "test@example.com"]);
+        run_git(dir, &["config", "user.name", "Test"]);
+    }
+
+    #[test]
+    fn test_gix_status_classifies_modified_deleted_and_untracked() {
+        let dir = unique_temp_dir("gix_status");
+        init_repo(&dir);
+
+        std::fs::write(dir.join("tracked.lua"), "return 1\n").unwrap();
+        std::fs::write(dir.join("deleted.lua"), "return 2\n").unwrap();
+        run_git(&dir, &["add", "."]);
+        run_git(&dir, &["commit", "-q", "-m", "initial"]);
+
+        // Modify a tracked file, delete another, and add one Git has never seen.
+        std::fs::write(dir.join("tracked.lua"), "return 3\n").unwrap();
+        std::fs::remove_file(dir.join("deleted.lua")).unwrap();
+        std::fs::write(dir.join("untracked.lua"), "return 4\n").unwrap();
+
+        let entry = RepoEntry::new(dir.clone(), "HEAD".to_owned());
+        entry.refresh().unwrap();
+
+        assert_eq!(
+            entry.status_for(&dir.join("tracked.lua")),
+            GitStatus::Modified
+        );
+        assert_eq!(
+            entry.status_for(&dir.join("deleted.lua")),
+            GitStatus::Deleted
+        );
+        assert_eq!(
+            entry.status_for(&dir.join("untracked.lua")),
+            GitStatus::Untracked
+        );
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_notify_git_dir_changed_refreshes_after_debounce() {
+        let root = unique_temp_dir("debounce_repo");
+        init_repo(&root);
+        std::fs::write(root.join("root.lua"), "return 1\n").unwrap();
+        run_git(&root, &["add", "."]);
+        run_git(&root, &["commit", "-q", "-m", "initial"]);
+
+        let filter = Arc::new(GitFilter::new(root.clone(), "HEAD".to_owned(), &root).unwrap());
+
+        std::fs::write(root.join("root.lua"), "return 2\n").unwrap();
+
+        // A single `git` operation touches several of these paths in quick
+        // succession; once the debounce window has passed, a background
+        // refresh has picked up the change they signal.
+        let git_dir = root.join(".git");
+        filter.notify_git_dir_changed(&git_dir.join("HEAD"));
+        filter.notify_git_dir_changed(&git_dir.join("index"));
+        filter.notify_git_dir_changed(&git_dir.join("HEAD"));
+
+        thread::sleep(GIT_DIR_DEBOUNCE * 3);
+
+        assert_eq!(
+            filter.status_for(&root.join("root.lua")),
+            GitStatus::Modified
+        );
+
+        std::fs::remove_dir_all(&root).ok();
+    }
+
+    #[test]
+    fn test_git_filter_discovers_and_routes_nested_repo() {
+        let root = unique_temp_dir("multi_repo_root");
+        init_repo(&root);
+        std::fs::write(root.join("root.lua"), "return 1\n").unwrap();
+        run_git(&root, &["add", "."]);
+        run_git(&root, &["commit", "-q", "-m", "initial"]);
+
+        let nested = root.join("vendor").join("nested");
+        std::fs::create_dir_all(&nested).unwrap();
+        init_repo(&nested);
+        std::fs::write(nested.join("nested.lua"), "return 2\n").unwrap();
+        run_git(&nested, &["add", "."]);
+        run_git(&nested, &["commit", "-q", "-m", "initial"]);
+        // An uncommitted change in the nested repo, so it has something to report.
+        std::fs::write(nested.join("nested.lua"), "return 3\n").unwrap();
+
+        let filter = GitFilter::new(root.clone(), "HEAD".to_owned(), &root).unwrap();
+
+        // A path under the nested repo is routed to (and lazily opens) its
+        // own `RepoEntry`, compared against its own history rather than the
+        // root repo's, and the root repo's own status is unaffected.
+        let nested_file = nested.join("nested.lua");
+        assert_eq!(filter.status_for(&nested_file), GitStatus::Modified);
+        assert!(filter.is_acknowledged(&nested_file));
+        assert_eq!(
+            filter.status_for(&root.join("root.lua")),
+            GitStatus::Unmodified
+        );
+
+        std::fs::remove_dir_all(&root).ok();
+    }
 }